@@ -6,7 +6,7 @@
 use std::time::{SystemTime, UNIX_EPOCH};
 use std::collections::HashMap;
 
-use chrono::prelude::NaiveDateTime;
+use chrono::prelude::{NaiveDateTime, NaiveDate, Datelike};
 use rusqlite::{Connection, params};
 
 
@@ -67,11 +67,14 @@ pub fn log_message(conn: &Connection, room: &str, message: LogEntry) -> Result<(
         return Ok(());
     }
 
-    let mut statement = conn.prepare(
+    // logs and logs_fts (an external-content FTS5 index) must stay in exact sync, so both
+    // inserts happen atomically — a failed logs_fts insert must not leave an unindexed row.
+    let tx = conn.unchecked_transaction()?;
+
+    let mut statement = tx.prepare(
         "INSERT INTO logs (timestamp, userid, username, type, roomid, body) VALUES (?, ?, ?, ?, ?, ?)"
     )?;
 
-
     statement.execute(params![
         SQLParameter::Number(message.time),
         message.sender_id,
@@ -80,51 +83,163 @@ pub fn log_message(conn: &Connection, room: &str, message: LogEntry) -> Result<(
         room,
         message.body,
     ])?;
+    drop(statement);
+
+    tx.execute(
+        "INSERT INTO logs_fts (rowid, body) VALUES (?, ?)",
+        params![tx.last_insert_rowid(), message.body],
+    )?;
+
+    tx.commit()?;
 
     Ok(())
 }
 
-/// Searches logs based on a variety of parameters.
-/// Output is formatted as HTML suitable for a Pokémon Showdown HTML box
-pub fn search(
-    conn: &Connection, room_id: &str, user_id: Option<&str>,
-    oldest: Option<i32>, keywords: Option<Vec<&str>>, max_messages: Option<i32>
-) -> Result<String, rusqlite::Error> {
-    let ranks = vec!['+', '^', '%', '@', '*', '#', '&', '~'];
+/// Which strategy [`search`] uses to match `keywords` against message bodies.
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub enum SearchMode {
+    /// `lower(body) LIKE '%keyword%'` — today's behavior. Scans every row but needs no index.
+    #[default]
+    Substring,
+    /// Matches keywords against the `logs_fts` FTS5 index for word-boundary matching
+    /// and much faster lookups over large logs. Results are still ordered by timestamp,
+    /// not by match rank.
+    FullText,
+}
 
-    let mut query_str = String::from("SELECT * FROM logs WHERE roomid = ?");
+/// What subset of logs a query should run over.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Scope<'a> {
+    /// A single room, e.g. "lobby"
+    Room(&'a str),
+    /// Every room the bot logs, for account-wide activity
+    AllRooms,
+    /// Global private messages only (`type = 'pm'`, where `roomid` is null)
+    PrivateMessages,
+}
+
+/// Filters accepted by [`search`], modeled on shell-history style filtering:
+/// a time range, keyword inclusion/exclusion, a `kind` restriction, and
+/// pagination/ordering knobs so callers can page through large result sets
+/// instead of only ever fetching the newest N messages.
+#[derive(Debug, Default)]
+pub struct SearchFilters<'a> {
+    pub user_id: Option<&'a str>,
+    /// Only return messages sent after this UNIX timestamp
+    pub after: Option<i32>,
+    /// Only return messages sent before this UNIX timestamp
+    pub before: Option<i32>,
+    pub keywords: Option<Vec<&'a str>>,
+    /// How `keywords` is matched against message bodies
+    pub mode: SearchMode,
+    /// Messages whose body contains any of these keywords are excluded
+    pub exclude_keywords: Option<Vec<&'a str>>,
+    /// Restrict to a message kind, e.g. "chat" or "pm"
+    pub kind: Option<&'a str>,
+    pub limit: Option<i32>,
+    pub offset: Option<i32>,
+    /// If true, order by timestamp ascending instead of the default descending
+    pub reverse: bool,
+}
+
+/// Builds the `SELECT ... WHERE ...` portion shared by [`search`] and [`search_chunked`],
+/// with `after`/`before` passed in separately so the latter can override the window
+/// per chunk without disturbing the rest of `filters`. `after_inclusive` lets
+/// `search_chunked` stitch chunks together with half-open bounds (`>=` on every chunk
+/// after the first) so a row landing exactly on a chunk boundary isn't dropped by both
+/// the `<` of the chunk before it and the `>` of the chunk after it.
+fn build_search_query(
+    select_clause: &str, scope: Scope, filters: &SearchFilters,
+    after: Option<i32>, after_inclusive: bool, before: Option<i32>
+) -> (String, Vec<SQLParameter>) {
+    let mut query_str = String::from(select_clause);
+    if filters.mode == SearchMode::FullText && filters.keywords.is_some() {
+        query_str.push_str(" JOIN logs_fts ON logs.log_id = logs_fts.rowid");
+    }
+    query_str.push_str(" WHERE 1=1");
     let mut args = Vec::<SQLParameter>::new();
-    args.push(SQLParameter::Text(room_id.to_owned()));
 
-    if let Some(id) = user_id {
-        query_str.push_str(" AND userid = ?");
+    match scope {
+        Scope::Room(room_id) => {
+            query_str.push_str(" AND logs.roomid = ?");
+            args.push(SQLParameter::Text(room_id.to_owned()));
+        }
+        Scope::AllRooms => {
+            query_str.push_str(" AND logs.roomid IS NOT NULL");
+        }
+        Scope::PrivateMessages => {
+            query_str.push_str(" AND logs.type = 'pm' AND logs.roomid IS NULL");
+        }
+    }
+
+    if let Some(id) = filters.user_id {
+        query_str.push_str(" AND logs.userid = ?");
         args.push(SQLParameter::Text(id.to_owned()));
     }
 
-    if let Some(keywords) = keywords {
+    if let Some(keywords) = &filters.keywords {
+        match filters.mode {
+            SearchMode::Substring => {
+                for keyword in keywords {
+                    query_str.push_str(" AND lower(logs.body) LIKE '%' || ? || '%'");
+                    args.push(SQLParameter::Text(keyword.to_lowercase()));
+                }
+            }
+            SearchMode::FullText => {
+                query_str.push_str(" AND logs_fts MATCH ?");
+                // Quote each keyword as a literal FTS5 phrase so ordinary search text
+                // (hyphens, colons, unbalanced quotes, AND/OR/NOT) can't be misread as
+                // query syntax the way it could with an unquoted MATCH argument.
+                let phrase = keywords.iter()
+                    .map(|k| format!("\"{}\"", k.replace('"', "\"\"")))
+                    .collect::<Vec<_>>()
+                    .join(" ");
+                args.push(SQLParameter::Text(phrase));
+            }
+        }
+    }
+
+    if let Some(keywords) = &filters.exclude_keywords {
         for keyword in keywords {
-            query_str.push_str(" AND lower(body) LIKE '%' || ? || '%'");
-            args.push(SQLParameter::Text(String::from(keyword).to_lowercase()));
+            query_str.push_str(" AND lower(logs.body) NOT LIKE '%' || ? || '%'");
+            args.push(SQLParameter::Text(keyword.to_lowercase()));
         }
     }
 
-    query_str.push_str(" AND timestamp > ? ORDER BY timestamp DESC LIMIT ?");
-    args.push(SQLParameter::Number(oldest.unwrap_or(0)));
-    args.push(SQLParameter::Number(max_messages.unwrap_or(1000)));
+    if let Some(kind) = filters.kind {
+        query_str.push_str(" AND logs.type = ?");
+        args.push(SQLParameter::Text(kind.to_owned()));
+    }
 
-    let mut statement = conn.prepare(&query_str)?;
+    if let Some(after) = after {
+        query_str.push_str(if after_inclusive {
+            " AND logs.timestamp >= ?"
+        } else {
+            " AND logs.timestamp > ?"
+        });
+        args.push(SQLParameter::Number(after));
+    }
 
-    // See https://github.com/hoodie/concatenation_benchmarks-rs for information on
-    // string concatenation performance in Rust.
-    // TL;DR .join()ing arrays or using push_str with a set-capacity String are best
-    let mut html = String::with_capacity(100000);
-    let mut rows = statement.query(args)?;
-    let mut current_day = String::from("");
+    if let Some(before) = before {
+        query_str.push_str(" AND logs.timestamp < ?");
+        args.push(SQLParameter::Number(before));
+    }
+
+    (query_str, args)
+}
+
+/// Renders the rows of a search query into `html`, carrying `current_day` across calls
+/// so per-day `<details>` grouping stays correct when rows arrive across several chunked
+/// queries instead of a single one. Does not close a trailing open `<details>` — callers
+/// close it once after the last chunk, since a new chunk may continue the same day.
+fn append_search_rows(
+    rows: &mut rusqlite::Rows, ranks: &[char], html: &mut String, current_day: &mut String
+) -> Result<(), rusqlite::Error> {
     while let Some(row) = rows.next()? {
         // row.get(1) -> timestamp
         let date = NaiveDateTime::from_timestamp(row.get(1).unwrap_or_else(|_| unix_time()), 0);
         let mdy = date.format("%v").to_string();
-        if current_day != mdy {
+        if current_day != &mdy {
             html.push_str(&[
                 if !current_day.is_empty() {
                     "</div></details>"
@@ -165,22 +280,172 @@ pub fn search(
             &(row.get(6).unwrap_or_else(|_| String::from("")) as String)
         ].join(""));
 
-        if current_day != mdy {
-            current_day = mdy;
+        if current_day != &mdy {
+            *current_day = mdy;
         }
     }
+    Ok(())
+}
+
+/// Searches logs based on a variety of parameters.
+/// Output is formatted as HTML suitable for a Pokémon Showdown HTML box
+pub fn search(
+    conn: &Connection, scope: Scope, filters: SearchFilters
+) -> Result<String, rusqlite::Error> {
+    let ranks = vec!['+', '^', '%', '@', '*', '#', '&', '~'];
+
+    let (mut query_str, mut args) = build_search_query(
+        "SELECT logs.* FROM logs", scope, &filters, filters.after, false, filters.before
+    );
+
+    query_str.push_str(if filters.reverse {
+        " ORDER BY logs.timestamp ASC, logs.log_id ASC LIMIT ? OFFSET ?"
+    } else {
+        " ORDER BY logs.timestamp DESC, logs.log_id ASC LIMIT ? OFFSET ?"
+    });
+    args.push(SQLParameter::Number(filters.limit.unwrap_or(1000)));
+    args.push(SQLParameter::Number(filters.offset.unwrap_or(0)));
+
+    let mut statement = conn.prepare(&query_str)?;
+
+    // See https://github.com/hoodie/concatenation_benchmarks-rs for information on
+    // string concatenation performance in Rust.
+    // TL;DR .join()ing arrays or using push_str with a set-capacity String are best
+    let mut html = String::with_capacity(100000);
+    let mut rows = statement.query(args)?;
+    let mut current_day = String::from("");
+    append_search_rows(&mut rows, &ranks, &mut html, &mut current_day)?;
     if !current_day.is_empty() {
         html.push_str("</div></details>");
     }
     Ok(html)
 }
 
-pub fn get_linecount(conn: &Connection, user_id: &str, room_id: &str, days: Option<i32>) -> Result<i32, rusqlite::Error> {
-    let days = days.unwrap_or(30);
+/// Number of days covered by each bounded query issued by [`search_chunked`].
+const SEARCH_CHUNK_DAYS: i32 = 14;
 
+/// Streams `search`-style results over a potentially huge `[after, before]` window without
+/// materializing the whole thing in memory. The window is split into fixed-size
+/// `SEARCH_CHUNK_DAYS`-day sub-ranges, one bounded query is issued per sub-range ordered by
+/// timestamp, and each chunk's rendered HTML is handed to `on_chunk` as soon as it's ready.
+/// Per-day `<details>` grouping is carried across chunk boundaries. `filters.limit`,
+/// `filters.offset`, and `filters.reverse` are ignored here; chunking always walks the
+/// window oldest-to-newest so results stream out in a stable order.
+///
+/// If a probe query shows the whole window has no matching rows, `on_chunk` is called once
+/// with a "not found" message and no further queries are issued.
+pub fn search_chunked<F: FnMut(&str)>(
+    conn: &Connection, scope: Scope, filters: SearchFilters, mut on_chunk: F
+) -> Result<(), rusqlite::Error> {
+    let ranks = vec!['+', '^', '%', '@', '*', '#', '&', '~'];
+    let window_start = filters.after.unwrap_or(0);
+    let window_end = filters.before.unwrap_or_else(|| unix_time() as i32);
+    let chunk_seconds = SEARCH_CHUNK_DAYS * 24 * 60 * 60;
+
+    let (count_query, count_args) = build_search_query(
+        "SELECT count(*) FROM logs", scope, &filters, Some(window_start), false, Some(window_end)
+    );
+    let total: i32 = conn.prepare(&count_query)?.query_row(count_args, |row| row.get(0))?;
+    if total == 0 {
+        on_chunk("Not found.");
+        return Ok(());
+    }
+
+    let mut current_day = String::from("");
+    let mut chunk_start = window_start;
+    while chunk_start < window_end {
+        let chunk_end = std::cmp::min(chunk_start + chunk_seconds, window_end);
+
+        let (mut query_str, args) = build_search_query(
+            "SELECT logs.* FROM logs", scope, &filters, Some(chunk_start), chunk_start != window_start, Some(chunk_end)
+        );
+        query_str.push_str(" ORDER BY logs.timestamp ASC");
+
+        let mut statement = conn.prepare(&query_str)?;
+        let mut rows = statement.query(args)?;
+        let mut html = String::new();
+        append_search_rows(&mut rows, &ranks, &mut html, &mut current_day)?;
+        if !html.is_empty() {
+            on_chunk(&html);
+        }
+
+        chunk_start = chunk_end;
+    }
+
+    if !current_day.is_empty() {
+        on_chunk("</div></details>");
+    }
+
+    Ok(())
+}
+
+pub fn get_linecount(conn: &Connection, user_id: &str, scope: Scope, days: Option<i32>) -> Result<i32, rusqlite::Error> {
+    let days = days.unwrap_or(30);
     let max_timestamp = unix_time() - (days * 24 * 60 * 60) as i64;
-    let mut statement = conn.prepare("SELECT count(log_id) FROM logs WHERE userid = ? AND roomid = ? AND timestamp > ?")?;
-    statement.query_row(params![user_id, room_id, max_timestamp], |row| row.get(0))
+
+    let mut query_str = String::from("SELECT count(log_id) FROM logs WHERE userid = ? AND timestamp > ?");
+    let mut args = Vec::<SQLParameter>::new();
+    args.push(SQLParameter::Text(user_id.to_owned()));
+    args.push(SQLParameter::Number(max_timestamp as i32));
+
+    match scope {
+        Scope::Room(room_id) => {
+            query_str.push_str(" AND roomid = ?");
+            args.push(SQLParameter::Text(room_id.to_owned()));
+        }
+        Scope::AllRooms => {
+            query_str.push_str(" AND roomid IS NOT NULL");
+        }
+        Scope::PrivateMessages => {
+            query_str.push_str(" AND type = 'pm' AND roomid IS NULL");
+        }
+    }
+
+    let mut statement = conn.prepare(&query_str)?;
+    statement.query_row(args, |row| row.get(0))
+}
+
+/// A calendar day that has at least one log entry, for building date-picker UIs
+#[derive(Debug, PartialEq)]
+pub struct LogDate {
+    pub year: i32,
+    pub month: u32,
+    pub day: u32,
+}
+
+/// Gets the distinct calendar days that have logs in a room, newest first.
+/// If `user_id` is given, only days with a message from that user are returned.
+pub fn available_log_dates(
+    conn: &Connection, room_id: &str, user_id: Option<&str>
+) -> Result<Vec<LogDate>, rusqlite::Error> {
+    let mut query_str = String::from(
+        "SELECT DISTINCT date(timestamp, 'unixepoch') AS day FROM logs WHERE roomid = ?"
+    );
+    let mut args = Vec::<SQLParameter>::new();
+    args.push(SQLParameter::Text(room_id.to_owned()));
+
+    if let Some(id) = user_id {
+        query_str.push_str(" AND userid = ?");
+        args.push(SQLParameter::Text(id.to_owned()));
+    }
+
+    query_str.push_str(" ORDER BY day DESC");
+
+    let mut statement = conn.prepare(&query_str)?;
+    let mut rows = statement.query(args)?;
+    let mut dates = Vec::new();
+    while let Some(row) = rows.next()? {
+        let day: String = row.get(0)?;
+        let date = NaiveDate::parse_from_str(&day, "%Y-%m-%d")
+            .expect("date() should always produce a parseable ISO date");
+        dates.push(LogDate {
+            year: date.year(),
+            month: date.month(),
+            day: date.day(),
+        });
+    }
+
+    Ok(dates)
 }
 
 /// Gets the users with the highest linecount in a room
@@ -188,7 +453,21 @@ pub fn get_linecount(conn: &Connection, user_id: &str, room_id: &str, days: Opti
 pub fn get_topusers(
     conn: &Connection, room_id: &str, days: Option<i32>, num_users: Option<i32>
 ) -> Result<HashMap<String, i32>, rusqlite::Error> {
-    Ok(HashMap::new())
+    let days = days.unwrap_or(30);
+    let num_users = num_users.unwrap_or(5);
+    let min_timestamp = unix_time() - (days * 24 * 60 * 60) as i64;
+
+    let mut statement = conn.prepare(
+        "SELECT userid, count(log_id) AS lines FROM logs WHERE roomid = ? AND timestamp > ? GROUP BY userid ORDER BY lines DESC LIMIT ?"
+    )?;
+
+    let mut rows = statement.query(params![room_id, min_timestamp, num_users])?;
+    let mut topusers = HashMap::new();
+    while let Some(row) = rows.next()? {
+        topusers.insert(row.get(0)?, row.get(1)?);
+    }
+
+    Ok(topusers)
 }
 
 /// Gets the users with the highest linecount in a room and formats them as HTML
@@ -196,7 +475,35 @@ pub fn get_topusers(
 pub fn get_topusers_html(
     conn: &Connection, room_id: &str, days: Option<i32>, num_users: Option<i32>
 ) -> Result<String, rusqlite::Error> {
-    Ok("".to_owned())
+    let days = days.unwrap_or(30);
+    let num_users = num_users.unwrap_or(5);
+    let min_timestamp = unix_time() - (days * 24 * 60 * 60) as i64;
+
+    let mut statement = conn.prepare(
+        "SELECT userid, count(log_id) AS lines FROM logs WHERE roomid = ? AND timestamp > ? GROUP BY userid ORDER BY lines DESC LIMIT ?"
+    )?;
+
+    let mut rows = statement.query(params![room_id, min_timestamp, num_users])?;
+    let mut list_items = String::new();
+    while let Some(row) = rows.next()? {
+        let userid: String = row.get(0)?;
+        let lines: i32 = row.get(1)?;
+        list_items.push_str(&[
+            "<li><strong>",
+            &html_escape::encode_text(&userid),
+            "</strong> — ",
+            &lines.to_string(),
+            if lines == 1 { " line</li>" } else { " lines</li>" },
+        ].join(""));
+    }
+
+    Ok([
+        "<details><summary>Top users in the room ",
+        room_id,
+        "</summary><ul>",
+        &list_items,
+        "</ul></details>",
+    ].join(""))
 }
 
 #[cfg(test)]
@@ -204,7 +511,9 @@ mod tests {
     use super::*;
     fn get_connection() -> Connection {
         let connection = Connection::open_in_memory().unwrap();
-        connection.execute(
+        // execute() only runs the first statement of a SQL string; execute_batch() is
+        // required here since this sets up several tables/indexes in one go.
+        connection.execute_batch(
             "CREATE TABLE IF NOT EXISTS logs (
                 log_id INTEGER NOT NULL PRIMARY KEY,
                 -- UNIX timestamp
@@ -224,8 +533,10 @@ mod tests {
             CREATE INDEX IF NOT EXISTS log_index_2 ON logs(userid, timestamp);
             CREATE INDEX IF NOT EXISTS log_index_3 ON logs(userid, roomid, timestamp);
             CREATE INDEX IF NOT EXISTS log_index_5 ON logs(type, userid, roomid, timestamp);
-            PRAGMA journal_mode=WAL;",
-            rusqlite::NO_PARAMS
+
+            CREATE VIRTUAL TABLE IF NOT EXISTS logs_fts USING fts5(body, content='logs', content_rowid='log_id');
+
+            PRAGMA journal_mode=WAL;"
         ).unwrap();
         connection
     }
@@ -294,30 +605,113 @@ mod tests {
         add_test_data(&conn, 1602131140)?;
 
         // Check that it can search by user ID and format regular users
-        let mut results = search(&conn, "test", Some("heartofetheria"), None, None, None)?;
+        let mut results = search(&conn, Scope::Room("test"), SearchFilters {
+            user_id: Some("heartofetheria"),
+            ..Default::default()
+        })?;
         // 19 Sep = 15 days ago as per add_test_data()
         assert_eq!(results, "<details style=\"margin-left: 5px;\"><summary><b>19-Sep-2020</b></summary><div style=\"margin-left: 10px;\"><small>[10:25:40] </small><b>Heart of Etheria</b>: Test Message Four</div></details>");
 
         // Check that it can format auth correctly
-        results = search(&conn, "test", Some("annika"), Some(0), None, Some(1))?;
+        results = search(&conn, Scope::Room("test"), SearchFilters {
+            user_id: Some("annika"),
+            after: Some(0),
+            limit: Some(1),
+            ..Default::default()
+        })?;
         assert_eq!(results, "<details style=\"margin-left: 5px;\"><summary><b> 8-Oct-2020</b></summary><div style=\"margin-left: 10px;\"><small>[04:25:40] </small><small>@</small><b>Annika</b>: Test Message One</div></details>");
 
-        // Check that it can search by time
-        results = search(&conn, "test", None, Some(1602131140 - 100), None, Some(1000))?;
+        // Check that it can search by a time range
+        results = search(&conn, Scope::Room("test"), SearchFilters {
+            after: Some(1602131140 - 100),
+            limit: Some(1000),
+            ..Default::default()
+        })?;
         assert_eq!(results.contains("Test Message One"), true);
         assert_eq!(results.contains("Test Message Two"), true);
         assert_eq!(results.contains("Test Message Three"), false);
         assert_eq!(results.contains("Test Message Four"), false);
 
+        results = search(&conn, Scope::Room("test"), SearchFilters {
+            before: Some(1602131140 - 100),
+            limit: Some(1000),
+            ..Default::default()
+        })?;
+        assert_eq!(results.contains("Test Message One"), false);
+        assert_eq!(results.contains("Test Message Two"), false);
+        assert_eq!(results.contains("Test Message Three"), true);
+        assert_eq!(results.contains("Test Message Four"), true);
+
         // Check that it can limit the number of messages returned
-        results = search(&conn, "test", None, None, None, Some(1))?;
+        results = search(&conn, Scope::Room("test"), SearchFilters {
+            limit: Some(1),
+            ..Default::default()
+        })?;
         assert_eq!(results.contains("Test Message One"), true);
         assert_eq!(results.contains("Test Message Two"), false);
         assert_eq!(results.contains("Test Message Three"), false);
         assert_eq!(results.contains("Test Message Four"), false);
 
+        // Check that it can page past the first result with offset
+        results = search(&conn, Scope::Room("test"), SearchFilters {
+            limit: Some(1),
+            offset: Some(1),
+            ..Default::default()
+        })?;
+        assert_eq!(results.contains("Test Message One"), false);
+        assert_eq!(results.contains("Test Message Two"), true);
+        assert_eq!(results.contains("Test Message Three"), false);
+        assert_eq!(results.contains("Test Message Four"), false);
+
         // Check that it can search by a (case-insensitive) keyword
-        results = search(&conn, "test", None, None, Some(vec!["tWo"]), None)?;
+        results = search(&conn, Scope::Room("test"), SearchFilters {
+            keywords: Some(vec!["tWo"]),
+            ..Default::default()
+        })?;
+        assert_eq!(results.contains("Test Message One"), false);
+        assert_eq!(results.contains("Test Message Two"), true);
+        assert_eq!(results.contains("Test Message Three"), false);
+        assert_eq!(results.contains("Test Message Four"), false);
+
+        // Check that it can exclude a (case-insensitive) keyword
+        results = search(&conn, Scope::Room("test"), SearchFilters {
+            exclude_keywords: Some(vec!["tWo"]),
+            ..Default::default()
+        })?;
+        assert_eq!(results.contains("Test Message One"), true);
+        assert_eq!(results.contains("Test Message Two"), false);
+        assert_eq!(results.contains("Test Message Three"), true);
+        assert_eq!(results.contains("Test Message Four"), true);
+
+        // Check that it can restrict by kind
+        results = search(&conn, Scope::Room("test"), SearchFilters {
+            kind: Some("pm"),
+            ..Default::default()
+        })?;
+        assert_eq!(results, "");
+
+        // Check that it can reverse the ordering
+        results = search(&conn, Scope::Room("test"), SearchFilters {
+            limit: Some(1),
+            reverse: true,
+            ..Default::default()
+        })?;
+        assert_eq!(results.contains("Test Message Three"), true);
+
+        Ok(())
+    }
+
+    #[test]
+    fn fulltext_search_test() -> Result<(), rusqlite::Error> {
+        let conn = get_connection();
+        add_test_data(&conn, 1602131140)?;
+
+        // Check that FullText mode matches on word boundaries via logs_fts
+        let results = search(&conn, Scope::Room("test"), SearchFilters {
+            keywords: Some(vec!["tWo"]),
+            mode: SearchMode::FullText,
+            ..Default::default()
+        })?;
         assert_eq!(results.contains("Test Message One"), false);
         assert_eq!(results.contains("Test Message Two"), true);
         assert_eq!(results.contains("Test Message Three"), false);
@@ -326,18 +720,114 @@ mod tests {
         Ok(())
     }
 
+    #[test]
+    fn search_chunked_test() -> Result<(), rusqlite::Error> {
+        let conn = get_connection();
+        add_test_data(&conn, 1602131140)?;
+
+        // Window tightly bracketing the test data, so chunking stays a handful of iterations
+        let mut chunks = Vec::new();
+        search_chunked(&conn, Scope::Room("test"), SearchFilters {
+            after: Some(1600511140 - 100),
+            before: Some(1602131140 + 100),
+            ..Default::default()
+        }, |chunk| chunks.push(chunk.to_owned()))?;
+
+        // The two older messages and the two current ones land in different chunks,
+        // but the day grouping should still only open one <details> per day overall
+        assert_eq!(chunks.iter().filter(|c| c.contains("<summary>")).count(), 2);
+        let combined = chunks.join("");
+        assert_eq!(combined.contains("Test Message One"), true);
+        assert_eq!(combined.contains("Test Message Two"), true);
+        assert_eq!(combined.contains("Test Message Three"), true);
+        assert_eq!(combined.contains("Test Message Four"), true);
+
+        // An empty window short-circuits to a single "not found" chunk
+        let mut empty_chunks = Vec::new();
+        search_chunked(&conn, Scope::Room("test"), SearchFilters {
+            after: Some(1602131140 + 1000),
+            ..Default::default()
+        }, |chunk| empty_chunks.push(chunk.to_owned()))?;
+        assert_eq!(empty_chunks, vec!["Not found.".to_owned()]);
+
+        Ok(())
+    }
+
+    #[test]
+    fn scope_test() -> Result<(), rusqlite::Error> {
+        let conn = get_connection();
+        let now = unix_time() as i32;
+        add_test_data(&conn, now)?;
+        log_message(&conn, "otherroom", LogEntry {
+            body: String::from("Test Message Five"),
+            kind: String::from("chat"),
+            sender_id: String::from("annika"),
+            sender_name: String::from("@Annika"),
+            time: now,
+        })?;
+        conn.execute(
+            "INSERT INTO logs (timestamp, userid, username, type, roomid, body) VALUES (?, ?, ?, 'pm', NULL, ?)",
+            params![now, "annika", "@Annika", "Test Message Six"],
+        )?;
+
+        // AllRooms sees messages from every room, but not PMs
+        let mut results = search(&conn, Scope::AllRooms, SearchFilters::default())?;
+        assert_eq!(results.contains("Test Message One"), true);
+        assert_eq!(results.contains("Test Message Five"), true);
+        assert_eq!(results.contains("Test Message Six"), false);
+
+        // Room stays scoped to just that room
+        results = search(&conn, Scope::Room("otherroom"), SearchFilters::default())?;
+        assert_eq!(results.contains("Test Message Five"), true);
+        assert_eq!(results.contains("Test Message One"), false);
+
+        // PrivateMessages only sees PMs
+        results = search(&conn, Scope::PrivateMessages, SearchFilters::default())?;
+        assert_eq!(results.contains("Test Message Six"), true);
+        assert_eq!(results.contains("Test Message One"), false);
+        assert_eq!(results.contains("Test Message Five"), false);
+
+        // get_linecount can be scoped the same way
+        assert_eq!(get_linecount(&conn, "annika", Scope::AllRooms, None), Ok(4));
+        assert_eq!(get_linecount(&conn, "annika", Scope::PrivateMessages, None), Ok(1));
+
+        Ok(())
+    }
+
     #[test]
     fn linecount_test() -> Result<(), rusqlite::Error> {
         let conn = get_connection();
         add_test_data(&conn, unix_time() as i32)?;
 
         // Test that it works
-        assert_eq!(get_linecount(&conn, "annika", "test", None), Ok(3));
-        assert_eq!(get_linecount(&conn, "heartofetheria", "test", None), Ok(1));
+        assert_eq!(get_linecount(&conn, "annika", Scope::Room("test"), None), Ok(3));
+        assert_eq!(get_linecount(&conn, "heartofetheria", Scope::Room("test"), None), Ok(1));
 
         // Test that it limits the number of days
-        assert_eq!(get_linecount(&conn, "annika", "test", Some(10)), Ok(2));
-        assert_eq!(get_linecount(&conn, "heartofetheria", "test", Some(10)), Ok(0));
+        assert_eq!(get_linecount(&conn, "annika", Scope::Room("test"), Some(10)), Ok(2));
+        assert_eq!(get_linecount(&conn, "heartofetheria", Scope::Room("test"), Some(10)), Ok(0));
+
+        // Test that it can search across every room
+        assert_eq!(get_linecount(&conn, "annika", Scope::AllRooms, None), Ok(3));
+
+        Ok(())
+    }
+
+    #[test]
+    fn available_log_dates_test() -> Result<(), rusqlite::Error> {
+        let conn = get_connection();
+        add_test_data(&conn, 1602131140)?;
+
+        // Test that it finds every distinct day, newest first
+        let dates = available_log_dates(&conn, "test", None)?;
+        assert_eq!(dates, vec![
+            LogDate { year: 2020, month: 10, day: 8 },
+            LogDate { year: 2020, month: 9, day: 19 },
+        ]);
+
+        // Test that it can restrict by user
+        let dates = available_log_dates(&conn, "test", Some("heartofetheria"))?;
+        assert_eq!(dates, vec![LogDate { year: 2020, month: 9, day: 19 }]);
 
         Ok(())
     }